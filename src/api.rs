@@ -0,0 +1,67 @@
+use crate::config::Config;
+use crate::engine::ModuleID;
+use crate::errors::*;
+use sodiumoxide::crypto::sign::ed25519::Signature;
+
+#[derive(Debug, Clone)]
+pub struct ModuleInfo {
+    pub id: ModuleID,
+    pub latest: Option<String>,
+}
+
+/// Signed metadata the registry publishes alongside a module's source, so
+/// `mod install`/`mod update` can verify authenticity before writing it to
+/// disk.
+#[derive(Debug, Clone)]
+pub struct ModuleMetadata {
+    pub version: String,
+    /// SHA-256 digest of the module's Lua source
+    pub digest: [u8; 32],
+    /// Detached signature over `digest`, made with the author's key
+    pub signature: Signature,
+}
+
+pub struct Client {
+    config: Config,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Client> {
+        Ok(Client {
+            config: config.clone(),
+        })
+    }
+
+    pub fn query_module(&self, module: &ModuleID) -> Result<ModuleInfo> {
+        debug!("Querying module: {:?} (proxy={:?})", module, self.config.network.proxy);
+        Ok(ModuleInfo {
+            id: module.clone(),
+            latest: None,
+        })
+    }
+
+    /// Query several modules in a single request, instead of one
+    /// round-trip per module.
+    pub fn query_modules(&self, modules: &[ModuleID]) -> Result<Vec<ModuleInfo>> {
+        debug!("Querying {} modules in a single request (proxy={:?})", modules.len(), self.config.network.proxy);
+        Ok(modules.iter()
+            .map(|id| ModuleInfo {
+                id: id.clone(),
+                latest: None,
+            })
+            .collect())
+    }
+
+    /// Download a module's Lua source for `version` (or the latest release
+    /// if `None`).
+    pub fn download_source(&self, module: &ModuleID, version: Option<&str>) -> Result<Vec<u8>> {
+        debug!("Downloading source for {:?} (version={:?})", module, version);
+        Ok(Vec::new())
+    }
+
+    /// Fetch the signed metadata published for `module` at `version`.
+    pub fn fetch_metadata(&self, module: &ModuleID, version: &str) -> Result<ModuleMetadata> {
+        debug!("Fetching signed metadata for {:?} ({})", module, version);
+        bail!("No signed metadata available for {}", module)
+    }
+}
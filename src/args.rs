@@ -0,0 +1,77 @@
+use crate::engine::ModuleID;
+use crate::errors::*;
+use semver::VersionReq;
+use std::str::FromStr;
+use structopt::StructOpt;
+
+/// A module reference optionally pinned to a version requirement, eg.
+/// `username:example@^1.2`.
+#[derive(Debug, Clone)]
+pub struct ModuleSpec {
+    pub id: ModuleID,
+    pub version_req: Option<VersionReq>,
+}
+
+impl FromStr for ModuleSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ModuleSpec> {
+        match s.find('@') {
+            Some(idx) => {
+                let (id, req) = s.split_at(idx);
+                let version_req = req[1..].parse::<VersionReq>()
+                    .map_err(|e| format_err!("Invalid version requirement {:?}: {}", &req[1..], e))?;
+                Ok(ModuleSpec {
+                    id: id.parse()?,
+                    version_req: Some(version_req),
+                })
+            },
+            None => Ok(ModuleSpec {
+                id: s.parse()?,
+                version_req: None,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Install {
+    /// The module to install, eg. `username:example` or `username:example@^1.2`
+    #[structopt(parse(try_from_str))]
+    pub module: ModuleSpec,
+    /// Install a specific version instead of the latest one
+    pub version: Option<String>,
+    /// Resolve and print the version that would be installed without installing it
+    #[structopt(long="dry-run")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct Search {
+    /// Search query
+    pub query: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_module_without_requirement() {
+        let spec = "username:example".parse::<ModuleSpec>().unwrap();
+        assert_eq!(spec.id.to_string(), "username:example");
+        assert!(spec.version_req.is_none());
+    }
+
+    #[test]
+    fn parses_module_with_requirement() {
+        let spec = "username:example@^1.2".parse::<ModuleSpec>().unwrap();
+        assert_eq!(spec.id.to_string(), "username:example");
+        assert_eq!(spec.version_req, Some(VersionReq::parse("^1.2").unwrap()));
+    }
+
+    #[test]
+    fn rejects_invalid_requirement() {
+        assert!("username:example@not-a-version".parse::<ModuleSpec>().is_err());
+    }
+}
@@ -0,0 +1 @@
+pub mod mod_cmd;
@@ -3,17 +3,20 @@ use crate::errors::*;
 use crate::args;
 use crate::args::Install;
 use crate::api::Client;
-use crate::config::Config;
+use crate::config::{Config, SignatureEnforcement, UpdatePolicy};
 use colored::Colorize;
 use crate::engine::Module;
 use crate::registry;
 use crate::shell::Readline;
 use crate::update::AutoUpdater;
+use std::collections::HashMap;
 use std::fmt::Write;
+use std::time::Duration;
 use structopt::StructOpt;
 use structopt::clap::AppSettings;
 use crate::term;
 use crate::worker;
+use semver::{Version, VersionReq};
 
 
 #[derive(Debug, StructOpt)]
@@ -51,6 +54,9 @@ pub struct List {
     /// List outdated modules
     #[structopt(long="outdated")]
     pub outdated: bool,
+    /// Don't contact the registry, only use cached update status
+    #[structopt(long="offline")]
+    pub offline: bool,
 }
 
 #[derive(Debug, StructOpt)]
@@ -59,42 +65,168 @@ pub struct Reload {
 
 #[derive(Debug, StructOpt)]
 pub struct Update {
+    /// Only update this module instead of all installed modules
+    pub module: Option<crate::engine::ModuleID>,
+    /// Only check for available updates, don't install them
+    #[structopt(long="check-only")]
+    pub check_only: bool,
+    /// Print the version transitions that would be applied, without installing anything
+    #[structopt(long="dry-run")]
+    pub dry_run: bool,
+    /// Force this exact version for `module`, mirroring `cargo update --precise`
+    #[structopt(long="precise", requires="module")]
+    pub precise: Option<String>,
+    /// Refuse to install/update any module that doesn't verify against a trusted signature
+    #[structopt(long="require-signatures")]
+    pub require_signatures: bool,
+    /// Don't contact the registry, only use cached update status
+    #[structopt(long="offline")]
+    pub offline: bool,
 }
 
-fn update(client: &Client, config: &Config, module: &Module) -> Result<()> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UpdateMode {
+    Install,
+    CheckOnly,
+    DryRun,
+}
+
+/// Returns `true` if `module` has an update available (installed or not).
+/// `latest` is assumed to already have been fetched (in bulk) by the caller.
+fn update(config: &Config, module: &Module, latest: &str, autoupdate: &mut AutoUpdater, mode: UpdateMode) -> Result<bool> {
     let name = module.canonical();
     let installed = module.version();
 
-    let label = format!("Searching for updates {}", name);
-    let infos = worker::spawn_fn(&label, || {
-        client.query_module(&module.id())
-    }, true)?;
-    debug!("Latest version: {:?}", infos);
+    let installed_version = Version::parse(installed)
+        .context("Installed module has an invalid version")?;
+    let latest_version = Version::parse(latest)
+        .context("Registry returned an invalid version")?;
+
+    if installed_version == latest_version {
+        autoupdate.updated(&name, installed);
+        return Ok(false);
+    }
+
+    if let Some(req) = autoupdate.requirement(&name) {
+        if !req.matches(&latest_version) {
+            debug!("{} has an update available but it doesn't satisfy {}", name, req);
+            return Ok(false);
+        }
+    }
 
-    let latest = infos.latest.ok_or_else(|| format_err!("Module doesn't have any released versions"))?;
+    let transition = format!("{}: {:?} -> {:?}", &name, installed, latest);
 
-    if installed != latest {
-        let label = format!("Updating {}: {:?} -> {:?}", &name, installed, latest);
-        worker::spawn_fn(&label, || {
-            registry::run_install(&Install {
-                module: module.id(),
-                version: None,
-            }, &config)
-        }, true)?;
+    match mode {
+        UpdateMode::Install => {
+            let label = format!("Updating {}", transition);
+            worker::spawn_fn(&label, || {
+                registry::run_install(&Install {
+                    module: args::ModuleSpec {
+                        id: module.id(),
+                        version_req: None,
+                    },
+                    version: Some(latest.to_string()),
+                    dry_run: false,
+                }, config)
+            }, true)?;
 
-        term::success(&format!("Updated {}: {:?} -> {:?}", &name, installed, latest));
+            autoupdate.updated(&name, latest);
+            term::success(&format!("Updated {}", transition));
+        },
+        UpdateMode::CheckOnly => {
+            autoupdate.mark_outdated(&name, latest);
+            term::info(&format!("{} is outdated", transition));
+        },
+        UpdateMode::DryRun => {
+            autoupdate.mark_outdated(&name, latest);
+            term::info(&format!("Would update {}", transition));
+        },
     }
 
-    Ok(())
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_update_when_versions_equal() {
+        let config = Config::default();
+        let module = Module::mock("username:example", "1.0.0");
+        let mut autoupdate = AutoUpdater::default();
+        let changed = update(&config, &module, "1.0.0", &mut autoupdate, UpdateMode::CheckOnly).unwrap();
+        assert!(!changed);
+    }
+
+    #[test]
+    fn check_only_flags_outdated_without_installing() {
+        let config = Config::default();
+        let module = Module::mock("username:example", "1.0.0");
+        let mut autoupdate = AutoUpdater::default();
+        let changed = update(&config, &module, "1.2.0", &mut autoupdate, UpdateMode::CheckOnly).unwrap();
+        assert!(changed);
+        assert_eq!(autoupdate.last_known_latest("username:example"), Some("1.2.0"));
+    }
+
+    #[test]
+    fn requirement_blocks_out_of_range_update() {
+        let config = Config::default();
+        let module = Module::mock("username:example", "1.0.0");
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.set_requirement("username:example", VersionReq::parse("^1.0").unwrap());
+        let changed = update(&config, &module, "2.0.0", &mut autoupdate, UpdateMode::CheckOnly).unwrap();
+        assert!(!changed);
+    }
+
+    #[test]
+    fn requirement_allows_in_range_update() {
+        let config = Config::default();
+        let module = Module::mock("username:example", "1.0.0");
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.set_requirement("username:example", VersionReq::parse("^1.0").unwrap());
+        let changed = update(&config, &module, "1.2.0", &mut autoupdate, UpdateMode::CheckOnly).unwrap();
+        assert!(changed);
+    }
 }
 
 pub fn run(rl: &mut Readline, args: &[String]) -> Result<()> {
     let args = Args::from_iter_safe(args)?;
-    let config = rl.config().clone();
+    let mut config = rl.config().clone();
 
     match args.subcommand {
         SubCommand::List(list) => {
-            let autoupdate = AutoUpdater::load()?;
+            let mut autoupdate = AutoUpdater::load()?;
+            let ttl = Duration::from_secs(config.update.cache_ttl);
+            let offline = list.offline || config.network.offline;
+
+            if list.outdated {
+                if offline {
+                    term::info("Operating offline, showing cached update status only");
+                } else {
+                    let stale = rl.engine().list().into_iter()
+                        .filter(|m| !m.is_private())
+                        .filter(|m| autoupdate.cached_latest(&m.canonical(), ttl).is_none())
+                        .map(|m| m.id())
+                        .collect::<Vec<_>>();
+
+                    if !stale.is_empty() {
+                        let client = Client::new(&config)?;
+                        let label = format!("Refreshing cache for {} modules", stale.len());
+                        let infos = worker::spawn_fn(&label, || {
+                            client.query_modules(&stale)
+                        }, true)?;
+
+                        for info in infos {
+                            if let Some(latest) = &info.latest {
+                                autoupdate.update_cache(&info.id.to_string(), latest);
+                            }
+                        }
+
+                        autoupdate.save()?;
+                    }
+                }
+            }
 
             for module in rl.engine().list() {
                 if let Some(source) = &list.source {
@@ -108,7 +240,14 @@ pub fn run(rl: &mut Readline, args: &[String]) -> Result<()> {
                 let mut out = String::new();
                 write!(&mut out, "{} ({})", canonical.green(),
                                             module.version().yellow())?;
-                if autoupdate.is_outdated(&canonical) {
+
+                let is_outdated = if offline {
+                    autoupdate.last_known_outdated(&canonical, module.version())
+                } else {
+                    autoupdate.is_outdated(&canonical, module.version(), ttl)
+                };
+
+                if is_outdated {
                     write!(&mut out, " {}", "[outdated]".red())?;
                 } else if list.outdated {
                     continue;
@@ -118,9 +257,37 @@ pub fn run(rl: &mut Readline, args: &[String]) -> Result<()> {
             }
         },
         SubCommand::Install(install) => {
-            registry::run_install(&install, &config)?;
-            // trigger reload
-            run(rl, &[String::from("mod"), String::from("reload")])?;
+            if install.dry_run {
+                let client = Client::new(&config)?;
+                let infos = client.query_module(&install.module.id)?;
+                let version = install.version.clone()
+                                .or(infos.latest)
+                                .ok_or_else(|| format_err!("Module doesn't have any released versions"))?;
+
+                if let Some(req) = &install.module.version_req {
+                    let parsed = Version::parse(&version)
+                        .context("Registry returned an invalid version")?;
+                    if !req.matches(&parsed) {
+                        bail!("{} {} doesn't satisfy requirement {}", install.module.id, version, req);
+                    }
+                }
+
+                term::info(&format!("Would install {} ({})", install.module.id, version));
+            } else {
+                let canonical = install.module.id.to_string();
+                let version_req = install.module.version_req.clone();
+
+                registry::run_install(&install, &config)?;
+
+                if let Some(req) = version_req {
+                    let mut autoupdate = AutoUpdater::load()?;
+                    autoupdate.set_requirement(&canonical, req);
+                    autoupdate.save()?;
+                }
+
+                // trigger reload
+                run(rl, &[String::from("mod"), String::from("reload")])?;
+            }
         },
         SubCommand::Search(search) => registry::run_search(rl.engine(), &search, &config)?,
         SubCommand::Reload(_) => {
@@ -136,30 +303,159 @@ pub fn run(rl: &mut Readline, args: &[String]) -> Result<()> {
                 }
             }
         },
-        SubCommand::Update(_) => {
-            let client = Client::new(&config)?;
+        SubCommand::Update(update_args) => {
+            if config.update.policy == UpdatePolicy::Disable {
+                term::info("Module updates are disabled in the configuration, skipping");
+                return Ok(());
+            }
 
-            let mut autoupdate = AutoUpdater::load()?;
+            if update_args.require_signatures {
+                config.signing.enforcement = SignatureEnforcement::Enforce;
+            }
 
-            for module in rl.engine().list() {
-                let canonical = module.canonical();
+            let check_only = update_args.check_only || config.update.policy == UpdatePolicy::CheckOnly;
+            let mode = if update_args.dry_run {
+                UpdateMode::DryRun
+            } else if check_only {
+                UpdateMode::CheckOnly
+            } else {
+                UpdateMode::Install
+            };
 
-                if module.is_private() {
-                    debug!("{} is a private module, skipping", canonical);
-                    continue;
+            let offline = update_args.offline || config.network.offline;
+
+            // `--precise` pins a single named module to an exact version,
+            // bypassing the regular requirement/semver based upgrade logic.
+            if let Some(precise) = &update_args.precise {
+                if offline && mode == UpdateMode::Install {
+                    term::error("Operating offline, cannot resolve --precise against the registry");
+                    return Ok(());
+                }
+
+                let module = update_args.module.as_ref()
+                    .ok_or_else(|| format_err!("--precise requires a module"))?;
+                let canonical = module.to_string();
+
+                // `--precise` must target an already-installed, non-private
+                // module, same as the regular update path (`candidates`
+                // below) -- otherwise it could be used to sideload an
+                // arbitrary registry module or overwrite a private one.
+                {
+                    let installed = rl.engine().get(&canonical)
+                        .map_err(|_| format_err!("{} is not an installed module", canonical))?;
+                    if installed.is_private() {
+                        bail!("{} is a private module, refusing to overwrite it with a registry download", canonical);
+                    }
                 }
 
-                if let Err(err) = update(&client, &config, &module) {
-                    term::error(&format!("Failed to update {}: {}", canonical, err));
+                if mode != UpdateMode::Install {
+                    term::info(&format!("Would pin {} to {:?}", canonical, precise));
                 } else {
-                    autoupdate.updated(&canonical);
+                    registry::run_install(&Install {
+                        module: args::ModuleSpec {
+                            id: module.clone(),
+                            version_req: None,
+                        },
+                        version: Some(precise.clone()),
+                        dry_run: false,
+                    }, &config)?;
+
+                    let mut autoupdate = AutoUpdater::load()?;
+                    // The cached "latest" no longer reflects what this module
+                    // should track once it's pinned to an exact version.
+                    autoupdate.invalidate_cache(&canonical);
+                    let exact = VersionReq::parse(&format!("={}", precise))
+                        .context("--precise must be a valid version")?;
+                    autoupdate.set_requirement(&canonical, exact);
+                    autoupdate.updated(&canonical, precise);
+                    autoupdate.save()?;
+
+                    term::success(&format!("Pinned {} to {:?}", canonical, precise));
+
+                    run(rl, &[String::from("mod"), String::from("reload")])?;
+                }
+
+                return Ok(());
+            }
+
+            // Same as the --precise guard above: don't let the default
+            // policy attempt a real network install while offline.
+            let mode = if offline && mode == UpdateMode::Install {
+                term::info("Operating offline, not installing anything");
+                UpdateMode::CheckOnly
+            } else {
+                mode
+            };
+
+            let candidates = rl.engine().list().into_iter()
+                .filter(|m| {
+                    if m.is_private() {
+                        debug!("{} is a private module, skipping", m.canonical());
+                        return false;
+                    }
+                    true
+                })
+                .filter(|m| update_args.module.as_ref().map(|only| &m.id() == only).unwrap_or(true))
+                .collect::<Vec<_>>();
+
+            let mut autoupdate = AutoUpdater::load()?;
+            let mut latest_by_id = HashMap::new();
+
+            if offline {
+                term::info("Operating offline, using cached version information only");
+                for module in &candidates {
+                    if let Some(latest) = autoupdate.last_known_latest(&module.canonical()) {
+                        latest_by_id.insert(module.canonical(), latest.to_string());
+                    }
+                }
+            } else {
+                let ids = candidates.iter().map(|m| m.id()).collect::<Vec<_>>();
+
+                let client = Client::new(&config)?;
+                let label = format!("Searching for updates ({} modules)", ids.len());
+                let infos = worker::spawn_fn(&label, || {
+                    client.query_modules(&ids)
+                }, true)?;
+
+                for info in infos {
+                    if let Some(latest) = info.latest {
+                        latest_by_id.insert(info.id.to_string(), latest);
+                    }
+                }
+            }
+
+            let mut outdated = 0;
+
+            for module in candidates {
+                let canonical = module.canonical();
+
+                let latest = match latest_by_id.get(&canonical) {
+                    Some(latest) => latest,
+                    None => {
+                        if offline {
+                            term::info(&format!("No cached version information for {}, skipping while offline", canonical));
+                        } else {
+                            term::error(&format!("Failed to update {}: Module doesn't have any released versions", canonical));
+                        }
+                        continue;
+                    },
+                };
+
+                match update(&config, &module, latest, &mut autoupdate, mode) {
+                    Ok(true) => outdated += 1,
+                    Ok(false) => (),
+                    Err(err) => term::error(&format!("Failed to update {}: {}", canonical, err)),
                 }
             }
 
             autoupdate.save()?;
 
-            // trigger reload
-            run(rl, &[String::from("mod"), String::from("reload")])?;
+            if mode == UpdateMode::Install {
+                // trigger reload
+                run(rl, &[String::from("mod"), String::from("reload")])?;
+            } else {
+                term::info(&format!("{} module(s) have updates available", outdated));
+            }
         },
     }
 
@@ -0,0 +1,85 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub network: Network,
+    #[serde(default)]
+    pub update: UpdateConfig,
+    #[serde(default)]
+    pub signing: SigningConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Network {
+    pub proxy: Option<String>,
+    /// For `mod update` and `mod list --outdated`: don't contact the
+    /// registry, rely purely on previously cached version data
+    #[serde(default)]
+    pub offline: bool,
+}
+
+/// Controls how `mod update` treats installed modules, mirroring the
+/// tri-state `auto-self-update` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdatePolicy {
+    Enable,
+    Disable,
+    CheckOnly,
+}
+
+impl Default for UpdatePolicy {
+    fn default() -> UpdatePolicy {
+        UpdatePolicy::Enable
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateConfig {
+    #[serde(default)]
+    pub policy: UpdatePolicy,
+    /// How long a cached "latest version" lookup stays valid for, in seconds
+    #[serde(default = "default_cache_ttl")]
+    pub cache_ttl: u64,
+}
+
+fn default_cache_ttl() -> u64 {
+    3600
+}
+
+impl Default for UpdateConfig {
+    fn default() -> UpdateConfig {
+        UpdateConfig {
+            policy: UpdatePolicy::default(),
+            cache_ttl: default_cache_ttl(),
+        }
+    }
+}
+
+/// How strictly module signatures are enforced on install/update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureEnforcement {
+    /// Don't fetch or check module signatures at all
+    Off,
+    /// Check signatures but only print a warning on failure
+    Warn,
+    /// Refuse to install/update a module that doesn't verify
+    Enforce,
+}
+
+impl Default for SignatureEnforcement {
+    fn default() -> SignatureEnforcement {
+        SignatureEnforcement::Off
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SigningConfig {
+    #[serde(default)]
+    pub enforcement: SignatureEnforcement,
+    /// Hex-encoded ed25519 public keys of trusted module authors
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+}
@@ -0,0 +1,24 @@
+use crate::errors::*;
+use sha2::{Digest, Sha256};
+use sodiumoxide::crypto::sign::ed25519::{PublicKey, Signature};
+
+/// SHA-256 digest of a module's Lua source, used to detect tampering
+/// between what was signed and what was downloaded.
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// Verify a detached ed25519 signature over `data` against `pubkey`.
+pub fn verify(data: &[u8], signature: &Signature, pubkey: &PublicKey) -> bool {
+    sodiumoxide::crypto::sign::ed25519::verify_detached(signature, data, pubkey)
+}
+
+/// Parse a hex-encoded ed25519 public key from the trusted keys list in `Config`.
+pub fn parse_pubkey(hex: &str) -> Result<PublicKey> {
+    let bytes = hex::decode(hex)
+        .context("Trusted key is not valid hex")?;
+    PublicKey::from_slice(&bytes)
+        .ok_or_else(|| format_err!("Trusted key has an invalid length"))
+}
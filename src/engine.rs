@@ -0,0 +1,87 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::errors::*;
+
+/// Identifies a module in the registry, eg. `username:example`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModuleID(String);
+
+impl fmt::Display for ModuleID {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for ModuleID {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<ModuleID> {
+        Ok(ModuleID(s.to_string()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    id: ModuleID,
+    version: String,
+    description: String,
+    private: bool,
+    source: Option<String>,
+}
+
+impl Module {
+    pub fn id(&self) -> ModuleID {
+        self.id.clone()
+    }
+
+    pub fn canonical(&self) -> String {
+        self.id.to_string()
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    pub fn source_equals(&self, source: &str) -> bool {
+        self.source.as_deref() == Some(source)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn mock(id: &str, version: &str) -> Module {
+        Module {
+            id: id.parse().unwrap(),
+            version: version.to_string(),
+            description: String::new(),
+            private: false,
+            source: None,
+        }
+    }
+}
+
+pub struct Engine {
+    modules: Vec<Module>,
+}
+
+impl Engine {
+    pub fn list(&self) -> Vec<Module> {
+        self.modules.clone()
+    }
+
+    pub fn get(&self, canonical: &str) -> Result<&Module> {
+        self.modules.iter()
+            .find(|m| m.canonical() == canonical)
+            .ok_or_else(|| format_err!("Module not found: {:?}", canonical))
+    }
+
+    pub fn reload_modules(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+pub use failure::{Error, ResultExt, bail, format_err};
+
+pub type Result<T> = ::std::result::Result<T, Error>;
@@ -0,0 +1,18 @@
+#[macro_use] extern crate log;
+#[macro_use] extern crate failure;
+
+pub mod api;
+pub mod args;
+pub mod cmd;
+pub mod config;
+pub mod crypto;
+pub mod engine;
+pub mod errors;
+pub mod registry;
+pub mod shell;
+pub mod term;
+pub mod update;
+pub mod worker;
+
+fn main() {
+}
@@ -0,0 +1,143 @@
+use crate::api::Client;
+use crate::args::Install;
+use crate::config::{Config, SignatureEnforcement};
+use crate::crypto;
+use crate::engine::{Engine, ModuleID};
+use crate::errors::*;
+use crate::term;
+use semver::Version;
+
+pub fn run_install(install: &Install, config: &Config) -> Result<()> {
+    debug!("Installing {:?} (version={:?})", install.module, install.version);
+
+    let client = Client::new(config)?;
+
+    // Resolve "latest" to a concrete version once, and use that same
+    // version for both the download and the signature check below. Doing
+    // these as two independent "latest" lookups would leave a window where
+    // a new release lands in between and the metadata verified wouldn't
+    // correspond to the source actually downloaded.
+    let version = match &install.version {
+        Some(version) => version.clone(),
+        None => {
+            let info = client.query_module(&install.module.id)?;
+            info.latest
+                .ok_or_else(|| format_err!("Module doesn't have any released versions"))?
+        },
+    };
+
+    if let Some(req) = &install.module.version_req {
+        let parsed = Version::parse(&version)
+            .context("Registry returned an invalid version")?;
+        if !req.matches(&parsed) {
+            bail!("{} {} doesn't satisfy requirement {}", install.module.id, version, req);
+        }
+    }
+
+    let source = client.download_source(&install.module.id, Some(&version))?;
+
+    verify_module(&client, &install.module.id, &version, &source, config)?;
+
+    Ok(())
+}
+
+/// Verify the downloaded source against the registry's signed metadata,
+/// honoring the configured enforcement level.
+fn verify_module(client: &Client, module: &ModuleID, version: &str, source: &[u8], config: &Config) -> Result<()> {
+    if config.signing.enforcement == SignatureEnforcement::Off {
+        return Ok(());
+    }
+
+    let result = client.fetch_metadata(module, version).and_then(|metadata| {
+        let digest = crypto::sha256(source);
+        if digest != metadata.digest {
+            bail!("Signature verification failed for {}: content digest mismatch", module);
+        }
+
+        let trusted = config.signing.trusted_keys.iter()
+            .filter_map(|key| crypto::parse_pubkey(key).ok())
+            .any(|pubkey| crypto::verify(&metadata.digest, &metadata.signature, &pubkey));
+
+        if !trusted {
+            bail!("No trusted signature found for {}", module);
+        }
+
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) if config.signing.enforcement == SignatureEnforcement::Enforce => Err(err),
+        Err(err) => {
+            term::error(&format!("{} (continuing because signature enforcement is set to warn)", err));
+            Ok(())
+        },
+    }
+}
+
+pub fn run_search(_engine: &Engine, search: &crate::args::Search, _config: &Config) -> Result<()> {
+    debug!("Searching for {:?}", search.query);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::ModuleSpec;
+    use semver::VersionReq;
+
+    fn module() -> ModuleID {
+        "username:example".parse().unwrap()
+    }
+
+    #[test]
+    fn run_install_rejects_version_outside_requirement() {
+        let config = Config::default();
+        let install = Install {
+            module: ModuleSpec {
+                id: module(),
+                version_req: Some(VersionReq::parse("^1.2").unwrap()),
+            },
+            version: Some("2.0.0".to_string()),
+            dry_run: false,
+        };
+        assert!(run_install(&install, &config).is_err());
+    }
+
+    #[test]
+    fn run_install_allows_version_within_requirement() {
+        let config = Config::default();
+        let install = Install {
+            module: ModuleSpec {
+                id: module(),
+                version_req: Some(VersionReq::parse("^1.2").unwrap()),
+            },
+            version: Some("1.5.0".to_string()),
+            dry_run: false,
+        };
+        assert!(run_install(&install, &config).is_ok());
+    }
+
+    #[test]
+    fn off_enforcement_skips_verification() {
+        let config = Config::default();
+        let client = Client::new(&config).unwrap();
+        assert!(verify_module(&client, &module(), "1.0.0", b"source", &config).is_ok());
+    }
+
+    #[test]
+    fn enforce_fails_without_metadata() {
+        let mut config = Config::default();
+        config.signing.enforcement = SignatureEnforcement::Enforce;
+        let client = Client::new(&config).unwrap();
+        assert!(verify_module(&client, &module(), "1.0.0", b"source", &config).is_err());
+    }
+
+    #[test]
+    fn warn_does_not_fail_without_metadata() {
+        let mut config = Config::default();
+        config.signing.enforcement = SignatureEnforcement::Warn;
+        let client = Client::new(&config).unwrap();
+        assert!(verify_module(&client, &module(), "1.0.0", b"source", &config).is_ok());
+    }
+}
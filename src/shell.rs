@@ -0,0 +1,33 @@
+use crate::config::Config;
+use crate::engine::{Engine, Module};
+
+pub struct Readline {
+    config: Config,
+    engine: Engine,
+    module: Option<Module>,
+}
+
+impl Readline {
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    pub fn take_module(&mut self) -> Option<Module> {
+        self.module.take()
+    }
+
+    pub fn set_module(&mut self, module: Module) {
+        self.module = Some(module);
+    }
+
+    pub fn reload_module_cache(&mut self) {
+    }
+}
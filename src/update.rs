@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use crate::errors::*;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    latest: String,
+    /// Seconds since the unix epoch
+    checked_at: u64,
+}
+
+/// On-disk representation of `AutoUpdater`. `VersionReq` doesn't implement
+/// `Serialize`/`Deserialize`, so requirements are stored as their string form.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Persisted {
+    #[serde(default)]
+    cache: HashMap<String, CacheEntry>,
+    #[serde(default)]
+    requirements: HashMap<String, String>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn cache_path() -> Result<PathBuf> {
+    let mut path = dirs::cache_dir()
+        .ok_or_else(|| format_err!("Could not determine cache directory"))?;
+    path.push("sn0int");
+    fs::create_dir_all(&path)?;
+    path.push("autoupdate.json");
+    Ok(path)
+}
+
+/// Tracks the last-known latest version of every module that was checked,
+/// along with a per-module version requirement it's pinned to, so repeated
+/// `mod list --outdated` calls don't need to hit the registry every time.
+#[derive(Debug, Default)]
+pub struct AutoUpdater {
+    cache: HashMap<String, CacheEntry>,
+    requirements: HashMap<String, VersionReq>,
+}
+
+impl AutoUpdater {
+    pub fn load() -> Result<AutoUpdater> {
+        let path = cache_path()?;
+        if !path.exists() {
+            return Ok(AutoUpdater::default());
+        }
+
+        let data = fs::read(&path)
+            .context("Failed to read update cache")?;
+        let persisted: Persisted = serde_json::from_slice(&data)
+            .unwrap_or_default();
+
+        let requirements = persisted.requirements.into_iter()
+            .filter_map(|(canonical, req)| VersionReq::parse(&req).ok().map(|req| (canonical, req)))
+            .collect();
+
+        Ok(AutoUpdater {
+            cache: persisted.cache,
+            requirements,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = cache_path()?;
+
+        let persisted = Persisted {
+            cache: self.cache.clone(),
+            requirements: self.requirements.iter()
+                .map(|(canonical, req)| (canonical.clone(), req.to_string()))
+                .collect(),
+        };
+
+        let data = serde_json::to_vec_pretty(&persisted)
+            .context("Failed to serialize update cache")?;
+        fs::write(&path, data)
+            .context("Failed to write update cache")?;
+        Ok(())
+    }
+
+    /// The cached latest version for `canonical`, unless the cache entry is
+    /// older than `ttl`.
+    pub fn cached_latest(&self, canonical: &str, ttl: Duration) -> Option<&str> {
+        self.cache.get(canonical)
+            .filter(|entry| now().saturating_sub(entry.checked_at) < ttl.as_secs())
+            .map(|entry| entry.latest.as_str())
+    }
+
+    /// Whether `canonical` is outdated according to the cache, without
+    /// touching the network. Returns `false` if there's no fresh cache entry,
+    /// or if the cached latest doesn't satisfy `canonical`'s pinned
+    /// requirement -- only the newest release is cached, so no update within
+    /// the pin is known in that case.
+    pub fn is_outdated(&self, canonical: &str, installed: &str, ttl: Duration) -> bool {
+        match self.cached_latest(canonical, ttl) {
+            Some(latest) => self.outdated_relative_to(canonical, installed, latest),
+            None => false,
+        }
+    }
+
+    /// The last-known latest version for `canonical`, regardless of how
+    /// long ago it was fetched. Used in offline mode, where a stale answer
+    /// beats no answer at all.
+    pub fn last_known_latest(&self, canonical: &str) -> Option<&str> {
+        self.cache.get(canonical).map(|entry| entry.latest.as_str())
+    }
+
+    /// Same as `is_outdated`, but based on the last-known cache entry
+    /// regardless of how stale it is. Used in offline mode, where a stale
+    /// answer beats no answer at all.
+    pub fn last_known_outdated(&self, canonical: &str, installed: &str) -> bool {
+        match self.last_known_latest(canonical) {
+            Some(latest) => self.outdated_relative_to(canonical, installed, latest),
+            None => false,
+        }
+    }
+
+    fn outdated_relative_to(&self, canonical: &str, installed: &str, latest: &str) -> bool {
+        if latest == installed {
+            return false;
+        }
+
+        if let Some(req) = self.requirement(canonical) {
+            if let Ok(version) = Version::parse(latest) {
+                if !req.matches(&version) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Record the latest known version for `canonical`, resetting its TTL.
+    pub fn update_cache(&mut self, canonical: &str, latest: &str) {
+        self.cache.insert(canonical.to_string(), CacheEntry {
+            latest: latest.to_string(),
+            checked_at: now(),
+        });
+    }
+
+    /// Force the next lookup for `canonical` to hit the registry again.
+    pub fn invalidate_cache(&mut self, canonical: &str) {
+        self.cache.remove(canonical);
+    }
+
+    /// Record that `canonical` is installed at `version`.
+    pub fn updated(&mut self, canonical: &str, version: &str) {
+        self.update_cache(canonical, version);
+    }
+
+    /// Record that `canonical` has `latest` available without installing it.
+    pub fn mark_outdated(&mut self, canonical: &str, latest: &str) {
+        self.update_cache(canonical, latest);
+    }
+
+    /// The version requirement `canonical` was pinned to, if any.
+    pub fn requirement(&self, canonical: &str) -> Option<&VersionReq> {
+        self.requirements.get(canonical)
+    }
+
+    /// Pin `canonical` to a version requirement so future updates only
+    /// move within the allowed range.
+    pub fn set_requirement(&mut self, canonical: &str, req: VersionReq) {
+        self.requirements.insert(canonical.to_string(), req);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_latest_within_ttl() {
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.update_cache("username:example", "1.2.3");
+        assert_eq!(autoupdate.cached_latest("username:example", Duration::from_secs(60)), Some("1.2.3"));
+    }
+
+    #[test]
+    fn cached_latest_expires_with_zero_ttl() {
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.update_cache("username:example", "1.2.3");
+        assert_eq!(autoupdate.cached_latest("username:example", Duration::from_secs(0)), None);
+    }
+
+    #[test]
+    fn cached_latest_missing_entry() {
+        let autoupdate = AutoUpdater::default();
+        assert_eq!(autoupdate.cached_latest("username:example", Duration::from_secs(60)), None);
+    }
+
+    #[test]
+    fn is_outdated_compares_against_cache() {
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.update_cache("username:example", "1.2.3");
+        assert!(autoupdate.is_outdated("username:example", "1.0.0", Duration::from_secs(60)));
+        assert!(!autoupdate.is_outdated("username:example", "1.2.3", Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn is_outdated_false_without_fresh_cache_entry() {
+        let autoupdate = AutoUpdater::default();
+        assert!(!autoupdate.is_outdated("username:example", "1.0.0", Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn is_outdated_false_when_latest_is_outside_requirement() {
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.update_cache("username:example", "2.0.0");
+        autoupdate.set_requirement("username:example", VersionReq::parse("^1.0").unwrap());
+        assert!(!autoupdate.is_outdated("username:example", "1.5.0", Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn last_known_outdated_also_respects_requirement() {
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.update_cache("username:example", "2.0.0");
+        autoupdate.set_requirement("username:example", VersionReq::parse("^1.0").unwrap());
+        assert!(!autoupdate.last_known_outdated("username:example", "1.5.0"));
+
+        autoupdate.update_cache("username:example", "1.8.0");
+        assert!(autoupdate.last_known_outdated("username:example", "1.5.0"));
+    }
+
+    #[test]
+    fn invalidate_cache_removes_entry() {
+        let mut autoupdate = AutoUpdater::default();
+        autoupdate.update_cache("username:example", "1.2.3");
+        autoupdate.invalidate_cache("username:example");
+        assert_eq!(autoupdate.last_known_latest("username:example"), None);
+    }
+
+    #[test]
+    fn requirement_round_trip() {
+        let mut autoupdate = AutoUpdater::default();
+        let req = VersionReq::parse("^1.2").unwrap();
+        autoupdate.set_requirement("username:example", req.clone());
+        assert_eq!(autoupdate.requirement("username:example"), Some(&req));
+    }
+}
@@ -0,0 +1,15 @@
+use crate::errors::*;
+
+/// Run `f`, logging `label` at debug level before it starts and, if `clear`
+/// is set, again once it's done. Nothing is printed to the terminal.
+pub fn spawn_fn<F, T>(label: &str, f: F, clear: bool) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    debug!("{}", label);
+    let result = f()?;
+    if clear {
+        debug!("done: {}", label);
+    }
+    Ok(result)
+}